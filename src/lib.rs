@@ -33,8 +33,10 @@
 //!     // Iterate through each dependency.  The dependencies will be returned
 //!     // in an order such that each output only depends on the previous
 //!     // outputs (or nothing).  The target itself will be output last.
+//!     // Each element yielded is a `Result`, as resolution may detect a
+//!     // dependency cycle.
 //!     for node in depgraph.satisfying_iter() {
-//!         print!("{} ", node);
+//!         print!("{} ", node.unwrap());
 //!     }
 //! }
 //! ```
@@ -51,7 +53,8 @@
 //! The algorithm is not deterministic, and may give a different answer each
 //! time it is run.  Beware.
 //!
-//! Dependency cycles are detected and will cause a panic!()
+//! Dependency cycles are detected and reported as a `SolventError::CycleDetected`
+//! carrying the offending nodes, rather than panicking.
 
 #![crate_name = "solvent"]
 #![crate_type = "lib"]
@@ -62,12 +65,113 @@
 
 extern crate log;
 
-use std::collections::{HashMap,HashSet};
+use std::collections::{HashMap,HashSet,BTreeSet};
 use std::collections::hash_map::{Occupied,Vacant};
 use std::iter::{Iterator};
 #[allow(unused_imports)]
 use std::task;
 
+pub use self::SolventError::CycleDetected;
+
+/// Errors that may occur while resolving a dependency graph.
+#[deriving(Show,Clone,PartialEq)]
+pub enum SolventError {
+    /// A dependency cycle was detected.  From the iterators, the attached
+    /// vector is the offending cycle in visitation order, with the repeated
+    /// node appearing at both ends (e.g. `["a","b","c","a"]`).  From
+    /// `toposort`, it holds every node left unresolved, sorted by name.
+    CycleDetected(Vec<String>),
+}
+
+/// (private) Maps node names to compact `u32` ids and back, so the hot
+/// resolution path can work on integer indices and bitsets instead of
+/// cloning `String` keys and `HashSet` lists at every step.
+#[deriving(Clone)]
+struct Interner {
+    ids: HashMap<String,u32>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    fn new() -> Interner
+    {
+        Interner { ids: HashMap::new(), names: Vec::new() }
+    }
+
+    /// Intern a name, returning its (possibly freshly allocated) id.
+    fn intern(&mut self, name: &str) -> u32
+    {
+        match self.ids.get(name) {
+            Some(id) => return *id,
+            None => {},
+        }
+        let id = self.names.len() as u32;
+        self.names.push( String::from_str(name) );
+        self.ids.insert( String::from_str(name), id );
+        id
+    }
+
+    /// Look up an existing id without interning.
+    fn get(&self, name: &str) -> Option<u32>
+    {
+        self.ids.get(name).map(|id| *id)
+    }
+
+    /// Map an id back to its name.
+    fn resolve(&self, id: u32) -> &str
+    {
+        self.names[id as uint].as_slice()
+    }
+}
+
+/// (private) A tiny growable bitset indexed by interned node id, used for
+/// the `satisfied` and `curpath` membership tests on the resolution path.
+#[deriving(Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new() -> BitSet
+    {
+        BitSet { words: Vec::new() }
+    }
+
+    fn ensure(&mut self, bit: uint)
+    {
+        let need = bit / 64 + 1;
+        while self.words.len() < need {
+            self.words.push(0u64);
+        }
+    }
+
+    fn insert(&mut self, bit: uint)
+    {
+        self.ensure(bit);
+        self.words[bit / 64] |= 1u64 << (bit % 64);
+    }
+
+    fn remove(&mut self, bit: uint)
+    {
+        if bit / 64 < self.words.len() {
+            self.words[bit / 64] &= !(1u64 << (bit % 64));
+        }
+    }
+
+    fn contains(&self, bit: uint) -> bool
+    {
+        let w = bit / 64;
+        w < self.words.len() && (self.words[w] & (1u64 << (bit % 64))) != 0
+    }
+
+    fn clear(&mut self)
+    {
+        for w in self.words.iter_mut() {
+            *w = 0u64;
+        }
+    }
+}
+
 /// This is the dependency graph.  It must be mutable, as the
 /// library uses internal properties in the graph to do its
 /// calculations.
@@ -80,11 +184,25 @@ pub struct DepGraph {
     // (private) target we are trying to satisfy
     target: Option<String>,
 
-    // (private) elements already satisfied
-    satisfied: HashSet<String>,
-
-    // (private) current path, for cycle detection
-    curpath: HashSet<String>,
+    // (private) interned node ids and the interned adjacency list
+    // (adjacency[id] = ids this node depends upon), kept in sync with
+    // `dependencies` at registration time.
+    interner: Interner,
+    adjacency: Vec<Vec<u32>>,
+
+    // (private) elements already satisfied, as a bitset over node ids
+    satisfied: BitSet,
+
+    // (private) soft ordering hints as (before, after) pairs.  Unlike
+    // dependencies these never pull a node into the resolution; they only
+    // influence the order when both endpoints are already included.
+    orderings: Vec<(String,String)>,
+
+    // (private) current path, for cycle detection: a bitset for O(1)
+    // membership plus an ordered stack so the actual cycle can be
+    // reconstructed on detection.
+    curpath: BitSet,
+    curstack: Vec<u32>,
 }
 
 /// This iterates through the dependencies of the DepGraph's target
@@ -106,8 +224,43 @@ impl DepGraph {
         DepGraph {
             dependencies: HashMap::new(),
             target: None,
-            curpath: HashSet::new(),
-            satisfied: HashSet::new(),
+            interner: Interner::new(),
+            adjacency: Vec::new(),
+            curpath: BitSet::new(),
+            curstack: Vec::new(),
+            satisfied: BitSet::new(),
+            orderings: Vec::new(),
+        }
+    }
+
+    /// Grow the interned adjacency list so that `id` has a (possibly empty)
+    /// dependency vector.
+    fn ensure_node(&mut self, id: u32)
+    {
+        while self.adjacency.len() <= id as uint {
+            self.adjacency.push(Vec::new());
+        }
+    }
+
+    /// Intern `node` and `dep` and record the interned edge, mirroring the
+    /// de-duplication done by the public `dependencies` map.
+    fn add_edge(&mut self, node: &str, dep: &str)
+    {
+        let nid = self.interner.intern(node);
+        let did = self.interner.intern(dep);
+        self.ensure_node(nid);
+        self.ensure_node(did);
+        if !self.adjacency[nid as uint].iter().any(|x| *x == did) {
+            self.adjacency[nid as uint].push(did);
+        }
+    }
+
+    /// Whether `name` has been marked satisfied.
+    fn is_satisfied(&self, name: &str) -> bool
+    {
+        match self.interner.get(name) {
+            Some(id) => self.satisfied.contains(id as uint),
+            None => false,
         }
     }
 
@@ -129,6 +282,7 @@ impl DepGraph {
                 (*entry.get_mut()).insert(String::from_str(depends_on));
             },
         }
+        self.add_edge(node, depends_on);
     }
 
     /// Add multiple dependencies of one node to a DepGraph.  The
@@ -153,6 +307,27 @@ impl DepGraph {
                 }
             },
         }
+        // Intern the node itself (it may have no dependencies) and record
+        // each interned edge.
+        let nid = self.interner.intern(node);
+        self.ensure_node(nid);
+        for s in depends_on.iter() {
+            self.add_edge(node, *s);
+        }
+    }
+
+    /// Register a soft ordering hint: if both `before` and `after` end up
+    /// in the output of `toposort`, `before` will be emitted ahead of
+    /// `after`.  The target iterators do not consult ordering hints.  Unlike
+    /// `register_dependency`, this does *not* require `before` to be present
+    /// and will never pull it into the dependency closure; it only affects
+    /// ordering when both endpoints are already included.  This is useful
+    /// for expressing scheduling preferences (e.g. plugin load order).
+    pub fn register_ordering<'a>( &mut self,
+                              before: &'a str,
+                              after: &'a str )
+    {
+        self.orderings.push( (String::from_str(before), String::from_str(after)) );
     }
 
     /// This sets the target node.  Iteratators on the graph always
@@ -168,30 +343,305 @@ impl DepGraph {
                                    nodes: &'a[&'a str] )
     {
         for node in nodes.iter() {
-            self.satisfied.insert(String::from_str(*node));
+            let id = self.interner.intern(*node);
+            self.ensure_node(id);
+            self.satisfied.insert(id as uint);
         }
     }
 
-    fn get_next_dependency(&mut self, node: &String) -> String
+    fn get_next_dependency(&mut self, node: &String) -> Result<String,SolventError>
+    {
+        // An un-interned target has no dependencies; it is its own answer.
+        let id = match self.interner.get(node.as_slice()) {
+            Some(id) => id,
+            None => return Ok(node.clone()),
+        };
+        self.resolve_id(id)
+    }
+
+    /// Id-based core of the resolver: walks the interned adjacency list
+    /// using bitsets for `satisfied`/`curpath` membership, so no per-step
+    /// `String`/`HashSet` cloning happens.  Names are reconstructed only
+    /// for the value that is returned.
+    fn resolve_id(&mut self, id: u32) -> Result<String,SolventError>
     {
-        if self.curpath.contains(node) {
-            panic!("Circular dependency graph at {}",node);
+        if self.curpath.contains(id as uint) {
+            // We are about to descend into a node already on the DFS stack;
+            // reconstruct the cycle from its first occurrence to the end and
+            // append the repeated node so the caller sees the full loop.
+            let mut cycle: Vec<String> = Vec::new();
+            match self.curstack.iter().position(|x| *x == id) {
+                Some(pos) => {
+                    for x in self.curstack.slice_from(pos).iter() {
+                        cycle.push( String::from_str(self.interner.resolve(*x)) );
+                    }
+                },
+                None => {},
+            }
+            cycle.push( String::from_str(self.interner.resolve(id)) );
+            return Err(CycleDetected(cycle));
+        }
+        self.curpath.insert(id as uint);
+        self.curstack.push(id);
+
+        // Find the first unsatisfied dependency, if any.
+        let mut next: Option<u32> = None;
+        if (id as uint) < self.adjacency.len() {
+            for dep in self.adjacency[id as uint].iter() {
+                if self.satisfied.contains(*dep as uint) {
+                    continue;
+                }
+                next = Some(*dep);
+                break;
+            }
         }
-        self.curpath.insert(node.clone());
 
-        let deplist = match self.dependencies.get(node) {
-            None => return node.clone(),
-            Some(deplist) => deplist.clone() // ouch
+        let result = match next {
+            Some(dep) => self.resolve_id(dep),
+            // node's dependencies are all satisfied
+            None => Ok( String::from_str(self.interner.resolve(id)) ),
         };
+        self.curstack.pop();
+        self.curpath.remove(id as uint);
+        result
+    }
 
-        for n in deplist.iter() {
-            if self.satisfied.contains(n) {
+    /// Produce a complete dependency-respecting ordering of every
+    /// registered node in a single pass, independent of any `target`.
+    /// Nodes already in the satisfied set are treated as removed and do
+    /// not appear in the output.
+    ///
+    /// This uses Kahn's algorithm with a lexicographically ordered ready
+    /// queue, so ties are broken by name and the result is stable and
+    /// reproducible.  If a cycle prevents a complete ordering, a
+    /// `CycleDetected` error is returned carrying the nodes left unresolved.
+    pub fn toposort(&self) -> Result<Vec<String>,SolventError>
+    {
+        // Collect every node mentioned, either as a dependant or as a
+        // dependency, that is not already satisfied.
+        let mut live: HashSet<String> = HashSet::new();
+        for (node, deps) in self.dependencies.iter() {
+            if !self.is_satisfied(node.as_slice()) {
+                live.insert(node.clone());
+            }
+            for dep in deps.iter() {
+                if !self.is_satisfied(dep.as_slice()) {
+                    live.insert(dep.clone());
+                }
+            }
+        }
+
+        // In-degree: how many live things each node depends on.  Build a
+        // reverse adjacency map at the same time so we can relax edges as
+        // nodes are emitted.
+        let mut indegree: HashMap<String,uint> = HashMap::new();
+        let mut rdeps: HashMap<String,Vec<String>> = HashMap::new();
+        for node in live.iter() {
+            indegree.insert(node.clone(), 0);
+        }
+        for (node, deps) in self.dependencies.iter() {
+            if !live.contains(node) {
                 continue;
             }
-            return self.get_next_dependency(n);
+            for dep in deps.iter() {
+                if !live.contains(dep) {
+                    continue;
+                }
+                *indegree.get_mut(node).unwrap() += 1;
+                match rdeps.entry( dep.clone() ) {
+                    Vacant(entry) => { entry.set( vec![node.clone()] ); },
+                    Occupied(mut entry) => { (*entry.get_mut()).push(node.clone()); },
+                }
+            }
+        }
+
+        // Fold in the soft ordering hints as extra edges, but only when both
+        // endpoints are already live: they reorder the result without
+        // inflating the dependency closure.
+        for &(ref before, ref after) in self.orderings.iter() {
+            if !live.contains(before) || !live.contains(after) {
+                continue;
+            }
+            *indegree.get_mut(after).unwrap() += 1;
+            match rdeps.entry( before.clone() ) {
+                Vacant(entry) => { entry.set( vec![after.clone()] ); },
+                Occupied(mut entry) => { (*entry.get_mut()).push(after.clone()); },
+            }
+        }
+
+        // Seed the ready queue with every zero-in-degree node.  The BTreeSet
+        // keeps ties broken lexicographically for a reproducible ordering.
+        let mut ready: BTreeSet<String> = BTreeSet::new();
+        for (node, deg) in indegree.iter() {
+            if *deg == 0 {
+                ready.insert(node.clone());
+            }
+        }
+
+        let mut output: Vec<String> = Vec::with_capacity(live.len());
+        loop {
+            let node = match ready.iter().next() {
+                Some(n) => n.clone(),
+                None => break,
+            };
+            ready.remove(&node);
+            output.push(node.clone());
+
+            match rdeps.get(&node) {
+                Some(dependants) => {
+                    for dependant in dependants.iter() {
+                        let zero = {
+                            let deg = indegree.get_mut(dependant).unwrap();
+                            *deg -= 1;
+                            *deg == 0
+                        };
+                        if zero {
+                            ready.insert(dependant.clone());
+                        }
+                    }
+                },
+                None => {},
+            }
+        }
+
+        if output.len() < live.len() {
+            // Whatever is left participates in (or hangs off of) a cycle.
+            let mut leftover: Vec<String> = live.iter()
+                .filter(|n| !output.contains(*n))
+                .map(|n| n.clone())
+                .collect();
+            leftover.sort();
+            return Err(CycleDetected(leftover));
+        }
+
+        Ok(output)
+    }
+
+    /// Render the dependency graph as a Graphviz DOT `digraph`, with one
+    /// edge per `node -> depends_on` pair, so large graphs can be
+    /// visualised and debugged.  Nodes already in the satisfied set are
+    /// greyed/filled and the current `target`, if set, is highlighted.
+    /// This reads the graph only and does not alter any state.
+    pub fn to_dot(&self) -> String
+    {
+        // Gather every node that appears, for stable, deduplicated decls.
+        let mut nodes: BTreeSet<String> = BTreeSet::new();
+        for (node, deps) in self.dependencies.iter() {
+            nodes.insert(node.clone());
+            for dep in deps.iter() {
+                nodes.insert(dep.clone());
+            }
+        }
+
+        let mut out = String::new();
+        out.push_str("digraph {\n");
+
+        for node in nodes.iter() {
+            let mut attrs: Vec<String> = Vec::new();
+            if self.is_satisfied(node.as_slice()) {
+                attrs.push(String::from_str("style=filled"));
+                attrs.push(String::from_str("fillcolor=grey"));
+            }
+            match self.target {
+                Some(ref t) if t == node => {
+                    attrs.push(String::from_str("color=red"));
+                    attrs.push(String::from_str("penwidth=2"));
+                },
+                _ => {},
+            }
+            if attrs.len() > 0 {
+                out.push_str(format!("    \"{}\" [{}];\n", node, attrs.as_slice().connect(",")).as_slice());
+            } else {
+                out.push_str(format!("    \"{}\";\n", node).as_slice());
+            }
+        }
+
+        // Edges, collected into a set first for a stable order.
+        let mut edges: BTreeSet<String> = BTreeSet::new();
+        for (node, deps) in self.dependencies.iter() {
+            for dep in deps.iter() {
+                edges.insert(format!("    \"{}\" -> \"{}\";", node, dep));
+            }
+        }
+        for edge in edges.iter() {
+            out.push_str(edge.as_slice());
+            out.push('\n');
+        }
+
+        out.push_str("}\n");
+        out
+    }
+
+    /// Return whether `to` is transitively reachable from `from` by
+    /// following dependency edges, i.e. whether `from` (directly or
+    /// indirectly) depends on `to`.  This is cycle-safe.
+    pub fn depends_on(&self, from: &str, to: &str) -> bool
+    {
+        self.path(from, to).is_some()
+    }
+
+    /// Return one concrete dependency chain from `from` to `to` (inclusive)
+    /// witnessing that `from` depends on `to`, or `None` if no such chain
+    /// exists.  The traversal is an iterative DFS guarded by a visited set,
+    /// so it terminates even on cyclic graphs, short-circuiting as soon as
+    /// `to` is reached and rebuilding the chain from recorded predecessors.
+    pub fn path(&self, from: &str, to: &str) -> Option<Vec<String>>
+    {
+        let from = String::from_str(from);
+        let to = String::from_str(to);
+
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut pred: HashMap<String,String> = HashMap::new();
+        let mut stack: Vec<String> = Vec::new();
+
+        // Seed with the direct dependencies of `from` so that a node is only
+        // deemed to depend on itself when a cycle actually leads back to it.
+        match self.dependencies.get(&from) {
+            Some(deps) => {
+                for dep in deps.iter() {
+                    if visited.insert(dep.clone()) {
+                        pred.insert(dep.clone(), from.clone());
+                        stack.push(dep.clone());
+                    }
+                }
+            },
+            None => {},
+        }
+
+        loop {
+            let node = match stack.pop() {
+                Some(n) => n,
+                None => return None,
+            };
+            if node == to {
+                // Walk the predecessors back up to `from`.
+                let mut chain: Vec<String> = vec![node.clone()];
+                let mut cur = node;
+                loop {
+                    match pred.get(&cur) {
+                        Some(p) => {
+                            chain.push(p.clone());
+                            if *p == from { break; }
+                            cur = p.clone();
+                        },
+                        None => break,
+                    }
+                }
+                chain.reverse();
+                return Some(chain);
+            }
+            match self.dependencies.get(&node) {
+                Some(deps) => {
+                    for dep in deps.iter() {
+                        if visited.insert(dep.clone()) {
+                            pred.insert(dep.clone(), node.clone());
+                            stack.push(dep.clone());
+                        }
+                    }
+                },
+                None => {},
+            }
         }
-        // nodes dependencies are satisfied
-        node.clone()
     }
 
     /// Get an iterator to iterate through the dependencies of
@@ -216,37 +666,46 @@ impl DepGraph {
     }
 }
 
-impl<'a> Iterator<String> for DepGraphIterator<'a> {
-    /// Get next dependency.  This may panic!() if a cycle is detected.
-    fn next(&mut self) -> Option<String>
+impl<'a> Iterator<Result<String,SolventError>> for DepGraphIterator<'a> {
+    /// Get next dependency.  Yields `Err(CycleDetected(..))` if a cycle is
+    /// detected rather than panicking.
+    fn next(&mut self) -> Option<Result<String,SolventError>>
     {
         let node = match self.depgraph.target {
             None => return None,
             Some(ref node) => node.clone()
         };
-        if self.depgraph.satisfied.contains(&node) {
+        if self.depgraph.is_satisfied(node.as_slice()) {
             return None;
         }
         self.depgraph.curpath.clear();
+        self.depgraph.curstack.clear();
         Some(self.depgraph.get_next_dependency(&node))
     }
 }
 
-impl<'a> Iterator<String> for DepGraphSatisfyingIterator<'a> {
-    /// Get next dependency.  This may panic!() if a cycle is detected.
-    fn next(&mut self) -> Option<String>
+impl<'a> Iterator<Result<String,SolventError>> for DepGraphSatisfyingIterator<'a> {
+    /// Get next dependency.  Yields `Err(CycleDetected(..))` if a cycle is
+    /// detected rather than panicking.  A node is only marked satisfied once
+    /// it has been emitted successfully.
+    fn next(&mut self) -> Option<Result<String,SolventError>>
     {
         let node = match self.depgraph.target {
             None => return None,
             Some(ref node) => node.clone()
         };
-        if self.depgraph.satisfied.contains(&node) {
+        if self.depgraph.is_satisfied(node.as_slice()) {
             return None;
         }
         self.depgraph.curpath.clear();
-        let next = self.depgraph.get_next_dependency(&node);
-        self.depgraph.mark_as_satisfied(&[next.as_slice()]);
-        Some(next)
+        self.depgraph.curstack.clear();
+        match self.depgraph.get_next_dependency(&node) {
+            Ok(next) => {
+                self.depgraph.mark_as_satisfied(&[next.as_slice()]);
+                Some(Ok(next))
+            },
+            Err(e) => Some(Err(e)),
+        }
     }
 }
 
@@ -273,7 +732,7 @@ fn solvent_test_branching() {
         assert!(results.len() < 30);
 
         let node = match depgraph.iter().next() {
-            Some(x) => x,
+            Some(x) => x.unwrap(),
             None => break,
         };
         depgraph.mark_as_satisfied(&[node.as_slice()]);
@@ -318,6 +777,7 @@ fn solvent_test_satisfying() {
     let mut results: Vec<String> = Vec::new();
 
     for node in depgraph.satisfying_iter() {
+        let node = node.unwrap();
         // detect infinite looping bugs
         assert!(results.len() < 30);
 
@@ -334,7 +794,6 @@ fn solvent_test_satisfying() {
 }
 
 #[test]
-#[should_fail]
 fn solvent_test_circular() {
 
     let mut depgraph: DepGraph = DepGraph::new();
@@ -343,22 +802,141 @@ fn solvent_test_circular() {
     depgraph.register_dependency("c","a");
     depgraph.set_target("a");
 
-    let mut results: Vec<String> = Vec::new();
+    // Resolution must not panic; it reports the offending cycle instead.
+    match depgraph.iter().next() {
+        Some(Err(CycleDetected(cycle))) => {
+            assert!( cycle == vec![String::from_str("a"),
+                                   String::from_str("b"),
+                                   String::from_str("c"),
+                                   String::from_str("a")] );
+        },
+        other => panic!("Expected a CycleDetected error, got {}", other),
+    }
+}
 
-    loop {
-        // Detect infinite looping bugs
-        // (Since this test should fail, we cause a success here)
-        if results.len() >= 30 { break; }
+#[test]
+fn solvent_test_toposort() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependencies("a",&["b","c","d"]);
+    depgraph.register_dependency("b","d");
+    depgraph.register_dependencies("c",&["e"]);
 
-        let node = match depgraph.iter().next() {
-            Some(x) => x,
-            None => break,
-        };
-        depgraph.mark_as_satisfied(&[node.as_slice()]);
-        results.push(node);
+    let order = depgraph.toposort().unwrap();
+
+    // Every dependency must precede the node that depends on it.
+    for (node, deps) in depgraph.dependencies.iter() {
+        let npos = order.iter().position(|n| n == node).unwrap();
+        for dep in deps.iter() {
+            let dpos = order.iter().position(|n| n == dep).unwrap();
+            assert!( dpos < npos );
+        }
+    }
+    // The ordering covers every live node.
+    assert!( order.len() == 5 );
+}
+
+#[test]
+fn solvent_test_toposort_satisfied() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependencies("a",&["b","c"]);
+    depgraph.register_dependency("b","d");
+    depgraph.mark_as_satisfied(&["d"]);
+
+    let order = depgraph.toposort().unwrap();
+    assert!( !order.contains(&String::from_str("d")) );
+    assert!( order.contains(&String::from_str("a")) );
+}
+
+#[test]
+fn solvent_test_toposort_cycle() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependency("a","b");
+    depgraph.register_dependency("b","c");
+    depgraph.register_dependency("c","a");
+
+    match depgraph.toposort() {
+        Err(CycleDetected(leftover)) => {
+            assert!( leftover == vec![String::from_str("a"),
+                                      String::from_str("b"),
+                                      String::from_str("c")] );
+        },
+        other => panic!("Expected a cycle, got {}", other),
     }
 }
 
+#[test]
+fn solvent_test_to_dot() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependencies("a",&["b","c"]);
+    depgraph.mark_as_satisfied(&["c"]);
+    depgraph.set_target("a");
+
+    let dot = depgraph.to_dot();
+    assert!( dot.as_slice().contains("digraph {") );
+    assert!( dot.as_slice().contains("\"a\" -> \"b\";") );
+    assert!( dot.as_slice().contains("\"a\" -> \"c\";") );
+    // The satisfied node is styled and the target is highlighted.
+    assert!( dot.as_slice().contains("\"c\" [style=filled,fillcolor=grey];") );
+    assert!( dot.as_slice().contains("color=red") );
+}
+
+#[test]
+fn solvent_test_reachability() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependencies("a",&["b","c"]);
+    depgraph.register_dependency("c","e");
+
+    assert!( depgraph.depends_on("a","e") );
+    assert!( depgraph.depends_on("a","b") );
+    assert!( !depgraph.depends_on("b","a") );
+    assert!( !depgraph.depends_on("a","z") );
+
+    let path = depgraph.path("a","e").unwrap();
+    assert!( path == vec![String::from_str("a"),
+                          String::from_str("c"),
+                          String::from_str("e")] );
+    assert!( depgraph.path("a","z").is_none() );
+}
+
+#[test]
+fn solvent_test_reachability_cycle_safe() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependency("a","b");
+    depgraph.register_dependency("b","c");
+    depgraph.register_dependency("c","a");
+
+    // Must terminate despite the cycle.
+    assert!( depgraph.depends_on("a","c") );
+    assert!( depgraph.depends_on("a","a") );
+}
+
+#[test]
+fn solvent_test_ordering_hint() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependencies("root",&["x","y"]);
+    // Without the hint, x and y tie and break lexicographically (x, y).
+    // This hint forces y ahead of x instead.
+    depgraph.register_ordering("y","x");
+
+    let order = depgraph.toposort().unwrap();
+    let xpos = order.iter().position(|n| n.as_slice() == "x").unwrap();
+    let ypos = order.iter().position(|n| n.as_slice() == "y").unwrap();
+    assert!( ypos < xpos );
+}
+
+#[test]
+fn solvent_test_ordering_hint_ignored_when_absent() {
+    let mut depgraph: DepGraph = DepGraph::new();
+    depgraph.register_dependency("root","x");
+    // `ghost` is never depended upon, so this hint must neither pull it
+    // into the resolution nor have any effect.
+    depgraph.register_ordering("ghost","x");
+
+    let order = depgraph.toposort().unwrap();
+    assert!( !order.contains(&String::from_str("ghost")) );
+    assert!( order.contains(&String::from_str("x")) );
+}
+
 #[test]
 fn solvent_test_satisfied_stoppage() {
 
@@ -383,7 +961,7 @@ fn solvent_test_satisfied_stoppage() {
         assert!(results.len() < 30);
 
         let node = match depgraph.iter().next() {
-            Some(x) => x,
+            Some(x) => x.unwrap(),
             None => break,
         };
         depgraph.mark_as_satisfied(&[node.as_slice()]);
@@ -391,4 +969,3 @@ fn solvent_test_satisfied_stoppage() {
     }
     assert!( !results.contains(&String::from_str("superconn")) );
 }
-